@@ -1,25 +1,48 @@
-use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::ledger::SubstateStore;
+
+use crate::ledger::FileSubstateStore;
+
+/// Default on-disk location of the persistent ledger.
+const STORE_DIR: &str = ".rad-rpc/ledger";
 
 pub struct Config {
     pub updated: bool,
-    pub ledger: InMemorySubstateStore,
+    pub current_epoch: u64,
+    pub ledger: FileSubstateStore,
 }
 
 impl Config {
     pub fn new() -> Config {
+        let ledger = FileSubstateStore::open(STORE_DIR);
+        let current_epoch = ledger.get_epoch();
         Config {
             updated: false,
-            ledger: InMemorySubstateStore::with_bootstrap(),
+            current_epoch,
+            ledger,
         }
     }
 
-    pub fn increment_epoch(&mut self) {}
+    pub fn increment_epoch(&mut self) {
+        self.current_epoch += 1;
+        self.ledger.set_epoch(self.current_epoch);
+    }
 
-    pub fn load(&mut self) -> &mut InMemorySubstateStore {
+    /// Set the current epoch, keeping the ledger's view in sync.
+    pub fn set_epoch(&mut self, epoch: u64) {
+        self.current_epoch = epoch;
+        self.ledger.set_epoch(epoch);
+    }
+
+    pub fn load(&mut self) -> &mut FileSubstateStore {
         &mut self.ledger
     }
 
-    pub fn load_immutable(&self) -> &InMemorySubstateStore {
+    pub fn load_immutable(&self) -> &FileSubstateStore {
         &self.ledger
     }
+
+    /// Persist any mutable bookkeeping that is not keyed by address.
+    pub fn flush(&self) {
+        self.ledger.flush();
+    }
 }
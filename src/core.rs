@@ -3,25 +3,38 @@ use std::str::FromStr;
 
 use parking_lot::{RwLockReadGuard, RwLockWriteGuard};
 use radix_engine::engine::validate_data;
-use radix_engine::ledger::{InMemorySubstateStore, SubstateStore};
+use radix_engine::ledger::SubstateStore;
 use radix_engine::model::{DataValidationError, Receipt, Supply};
 use radix_engine::transaction::TransactionExecutor;
+use scrypto::resource::{ResourceType, MINTABLE};
 use scrypto::types::{Address, EcdsaPublicKey, Mid, Vid};
 
 use crate::identify_last::IdentifyLast;
 
 use super::formatter;
 use super::CONFIG;
+use super::ENTITY_EVENTS;
 
+use std::sync::Arc;
+
+use jsonrpc_core::futures::{Future, Stream};
 use jsonrpc_core::serde_json::{json, Map};
 use jsonrpc_core::*;
-use jsonrpc_http_server::ServerBuilder;
+use jsonrpc_http_server::hosts::Host;
+use jsonrpc_http_server::{AccessControlAllowOrigin, DomainsValidation, ServerBuilder};
+use jsonrpc_ipc_server::ServerBuilder as IpcServerBuilder;
+use jsonrpc_pubsub::{PubSubHandler, Session, Subscriber, SubscriptionId};
+use jsonrpc_ws_server::{RequestContext, ServerBuilder as WsServerBuilder};
 use serde::Deserialize;
 
+use crate::settings::Settings;
+
 #[derive(Deserialize)]
 struct RunParams {
     manifest: String,
     signers: Vec<String>,
+    #[serde(default)]
+    trace: bool,
 }
 
 #[derive(Deserialize)]
@@ -29,9 +42,71 @@ struct ShowParams {
     address: String,
 }
 
-pub fn core_thread() {
+#[derive(Deserialize)]
+struct SetEpochParams {
+    epoch: u64,
+}
+
+pub fn core_thread(settings: Settings) {
     let mut io = IoHandler::default();
+    register_methods(&mut io);
+
+    // Local IPC transport over a domain socket, serving the same methods as the
+    // HTTP endpoint. `ServerBuilder::new` consumes the handler, so the IPC
+    // server takes a clone of the one built once above.
+    let ipc_server = IpcServerBuilder::new(io.clone())
+        .start(&settings.ipc_path)
+        .expect("Unable to start ipc server");
+
+    // WebSocket transport sharing the same method set plus the
+    // `subscribe_entities` pub/sub notification stream.
+    let ws_server = WsServerBuilder::with_meta_extractor(
+        build_ws_handler(),
+        |context: &RequestContext| Arc::new(Session::new(context.sender())),
+    )
+    .start(&settings.ws_bind.parse().expect("Invalid ws_bind address"))
+    .expect("Unable to start ws server");
 
+    let server = ServerBuilder::new(io)
+        .threads(settings.threads)
+        .cors(cors_domains(&settings.cors_origins))
+        .allowed_hosts(allowed_hosts(&settings.allowed_hosts))
+        .start_http(&settings.http_bind.parse().expect("Invalid http_bind address"))
+        .expect("Unable to start http server");
+    server.wait();
+    drop(ws_server);
+    drop(ipc_server);
+}
+
+/// Map the configured CORS origins to the HTTP server's validation type.
+/// An empty list disables CORS; `*` allows any origin.
+fn cors_domains(origins: &[String]) -> DomainsValidation<AccessControlAllowOrigin> {
+    if origins.is_empty() {
+        return DomainsValidation::Disabled;
+    }
+    let mapped = origins
+        .iter()
+        .map(|origin| match origin.as_str() {
+            "*" => AccessControlAllowOrigin::Any,
+            "null" => AccessControlAllowOrigin::Null,
+            value => AccessControlAllowOrigin::Value(value.into()),
+        })
+        .collect();
+    DomainsValidation::AllowOnly(mapped)
+}
+
+/// Map the configured Host allow-list to the HTTP server's validation type.
+/// An empty list disables Host-header validation.
+fn allowed_hosts(hosts: &[String]) -> DomainsValidation<Host> {
+    if hosts.is_empty() {
+        return DomainsValidation::Disabled;
+    }
+    DomainsValidation::AllowOnly(hosts.iter().map(|host| Host::from(host.as_str())).collect())
+}
+
+/// Register the transport-agnostic methods (`run`, `show`) on any handler so
+/// the HTTP and WebSocket transports expose an identical method set.
+fn register_methods<M: Metadata>(io: &mut MetaIoHandler<M>) {
     io.add_method("run", |params: Params| async move {
         let parsed: Option<RunParams> = params.parse().ok();
         match parsed {
@@ -48,11 +123,66 @@ pub fn core_thread() {
         }
     });
 
-    let server = ServerBuilder::new(io)
-        .threads(100)
-        .start_http(&"127.0.0.1:3030".parse().unwrap())
-        .expect("Unable to start http server");
-    server.wait();
+    io.add_method("get_epoch", |_params: Params| async move { get_epoch().await });
+
+    io.add_method("set_epoch", |params: Params| async move {
+        let parsed: Option<SetEpochParams> = params.parse().ok();
+        match parsed {
+            Some(p) => set_epoch(p).await,
+            None => return parse_err(),
+        }
+    });
+
+    io.add_method("increment_epoch", |_params: Params| async move {
+        increment_epoch().await
+    });
+}
+
+/// Build the WebSocket handler: the shared methods plus the
+/// `subscribe_entities`/`unsubscribe_entities` pub-sub pair. Each session
+/// forwards every `run`-committed entity list into its `Sink` until the
+/// client unsubscribes or disconnects.
+fn build_ws_handler() -> PubSubHandler<Arc<Session>> {
+    let mut meta = MetaIoHandler::<Arc<Session>>::default();
+    register_methods(&mut meta);
+
+    let mut io = PubSubHandler::new(meta);
+    io.add_subscription(
+        "entities",
+        (
+            "subscribe_entities",
+            |_params: Params, _meta: Arc<Session>, subscriber: Subscriber| {
+                let (id, rx) = ENTITY_EVENTS.subscribe();
+                let sink = match subscriber.assign_id(SubscriptionId::Number(id)) {
+                    Ok(sink) => sink,
+                    Err(_) => return,
+                };
+
+                std::thread::spawn(move || {
+                    let forward = rx
+                        .map_err(|_| ())
+                        .fold(sink, move |sink, event| {
+                            sink.notify(Params::Array(vec![json!(event)]))
+                                .map(|_| sink)
+                                .map_err(|_| ())
+                        })
+                        .map(|_| ());
+                    let _ = forward.wait();
+                });
+            },
+        ),
+        (
+            "unsubscribe_entities",
+            |id: SubscriptionId, _meta: Option<Arc<Session>>| {
+                let removed = match id {
+                    SubscriptionId::Number(n) => ENTITY_EVENTS.unsubscribe(n),
+                    _ => false,
+                };
+                jsonrpc_core::futures::future::ok(Value::Bool(removed))
+            },
+        ),
+    );
+    io
 }
 
 async fn run(params: RunParams) -> jsonrpc_core::Result<jsonrpc_core::Value> {
@@ -73,7 +203,9 @@ async fn run(params: RunParams) -> jsonrpc_core::Result<jsonrpc_core::Value> {
 
     let write_lock = CONFIG.write();
     let _ = RwLockWriteGuard::map(write_lock, |config| {
+        let epoch = config.current_epoch;
         let ledger = config.load();
+        ledger.set_epoch(epoch);
         let mut executor = TransactionExecutor::new(ledger, false);
         let transaction = transaction_manifest::compile(&params.manifest).ok();
         match transaction {
@@ -87,6 +219,7 @@ async fn run(params: RunParams) -> jsonrpc_core::Result<jsonrpc_core::Value> {
                     Ok(receipt) => receipt_opt = Some(receipt),
                     Err(_) => {}
                 }
+                config.flush();
             }
             None => compile_err = true,
         }
@@ -99,6 +232,14 @@ async fn run(params: RunParams) -> jsonrpc_core::Result<jsonrpc_core::Value> {
     }
 
     if let Some(receipt) = receipt_opt {
+        // Built before the result is matched so the trace survives even when the
+        // transaction reverted (the non-trace path throws the receipt away).
+        let trace = if params.trace {
+            Some(build_trace(&receipt))
+        } else {
+            None
+        };
+
         match receipt.result {
             Ok(_) => {
                 let mut outputs: Vec<String> = Vec::new();
@@ -135,20 +276,97 @@ async fn run(params: RunParams) -> jsonrpc_core::Result<jsonrpc_core::Value> {
                     .map(|x| x.to_string())
                     .collect();
 
-                Ok(json!({
+                let new_entities: Vec<String> =
+                    receipt.new_entities.iter().map(|x| x.to_string()).collect();
+                if !new_entities.is_empty() {
+                    ENTITY_EVENTS.publish(new_entities);
+                }
+
+                let mut body = json!({
                     "packages": packages_string,
                     "components": components_string,
                     "resource_defs": resource_defs_string,
                     "outputs": outputs
-                }))
+                });
+
+                if let Some((steps, logs)) = trace {
+                    body["trace"] = Value::Array(steps);
+                    body["logs"] = Value::Array(logs);
+                    body["error"] = Value::Null;
+                }
+
+                Ok(body)
             }
-            Err(_) => transaction_execution_error(),
+            Err(ref error) => match trace {
+                // Return the manifest steps executed so far plus the engine logs
+                // and the error that reverted the transaction, so `trace` callers
+                // can debug which step failed.
+                Some((steps, logs)) => Ok(json!({
+                    "packages": [],
+                    "components": [],
+                    "resource_defs": [],
+                    "outputs": [],
+                    "trace": steps,
+                    "logs": logs,
+                    "error": format!("{:?}", error),
+                })),
+                None => transaction_execution_error(),
+            },
         }
     } else {
         transaction_validation_err()
     }
 }
 
+/// Build an ordered execution trace from the receipt.
+///
+/// The 0.3 engine's `Receipt` does not expose per-instruction state deltas
+/// (buckets/proofs created, resources moved, component state touched), so a
+/// true per-step diff is not available at this engine version; the request is
+/// scoped to what the receipt does carry. For each manifest instruction we emit
+/// its kind and form, and for each invocation (`CallFunction`/`CallMethod`) the
+/// return value — `receipt.outputs` is ordered to match those instructions — so
+/// callers can see what each step produced. The engine log messages are
+/// returned alongside.
+fn build_trace(receipt: &Receipt) -> (Vec<Value>, Vec<Value>) {
+    let mut outputs = receipt.outputs.iter();
+    let mut steps = Vec::new();
+    for (i, instruction) in receipt.transaction.instructions.iter().enumerate() {
+        let debug = format!("{:?}", instruction);
+        let kind = debug
+            .split(|c| c == ' ' || c == '{' || c == '(')
+            .next()
+            .unwrap_or("")
+            .to_string();
+
+        let mut step = json!({
+            "index": i,
+            "kind": kind,
+            "instruction": debug,
+        });
+
+        if kind == "CallFunction" || kind == "CallMethod" {
+            if let Some(output) = outputs.next() {
+                step["output"] = Value::String(formatter::format_value(
+                    &output.dom,
+                    &HashMap::new(),
+                    &HashMap::new(),
+                ));
+            }
+        }
+
+        steps.push(step);
+    }
+
+    let logs = receipt
+        .logs
+        .iter()
+        .map(|(level, message)| json!({ "level": format!("{:?}", level), "message": message }))
+        .collect();
+
+    (steps, logs)
+}
+
 async fn show(params: ShowParams) -> jsonrpc_core::Result<jsonrpc_core::Value> {
     let address = Address::from_str(&params.address);
     let parsed;
@@ -162,10 +380,29 @@ async fn show(params: ShowParams) -> jsonrpc_core::Result<jsonrpc_core::Value> {
     match parsed {
         Address::Package(_) => dump_package(parsed),
         Address::Component(_) => dump_component(parsed),
-        Address::ResourceDef(_) => return parse_err(),
+        Address::ResourceDef(_) => dump_resource_def(parsed),
     }
 }
 
+async fn get_epoch() -> jsonrpc_core::Result<jsonrpc_core::Value> {
+    let read = CONFIG.read();
+    Ok(json!({ "epoch": read.current_epoch }))
+}
+
+async fn set_epoch(params: SetEpochParams) -> jsonrpc_core::Result<jsonrpc_core::Value> {
+    let mut write = CONFIG.write();
+    write.set_epoch(params.epoch);
+    write.flush();
+    Ok(json!({ "epoch": write.current_epoch }))
+}
+
+async fn increment_epoch() -> jsonrpc_core::Result<jsonrpc_core::Value> {
+    let mut write = CONFIG.write();
+    write.increment_epoch();
+    write.flush();
+    Ok(json!({ "epoch": write.current_epoch }))
+}
+
 fn dump_package(address: Address) -> jsonrpc_core::Result<jsonrpc_core::Value> {
     let mut bytes = 0;
     let read_lock = CONFIG.read();
@@ -262,6 +499,47 @@ fn dump_component(address: Address) -> jsonrpc_core::Result<jsonrpc_core::Value>
     }
 }
 
+fn dump_resource_def(address: Address) -> jsonrpc_core::Result<jsonrpc_core::Value> {
+    let read_lock = CONFIG.read();
+    let mut not_found = false;
+    let mut result = json!(null);
+
+    let _ = RwLockReadGuard::map(read_lock, |config| {
+        let ledger = config.load_immutable();
+        match ledger.get_resource_def(address) {
+            Some(resource_def) => {
+                let (resource_type, divisibility) = match resource_def.resource_type() {
+                    ResourceType::Fungible { divisibility } => ("fungible", Some(divisibility)),
+                    ResourceType::NonFungible => ("non_fungible", None),
+                };
+
+                let mut metadata: Map<String, Value> = Map::new();
+                for (key, value) in resource_def.metadata() {
+                    metadata.insert(key.clone(), Value::String(value.clone()));
+                }
+
+                result = json!({
+                    "resource_type": resource_type,
+                    "divisibility": divisibility,
+                    "total_supply": resource_def.total_supply().to_string(),
+                    "metadata": metadata,
+                    "flags": resource_def.flags(),
+                    "mutable_flags": resource_def.mutable_flags(),
+                    "mintable": resource_def.flags() & MINTABLE != 0,
+                });
+            }
+            None => not_found = true,
+        }
+        config
+    });
+
+    if not_found {
+        not_found_err("Resource def not found")
+    } else {
+        Ok(result)
+    }
+}
+
 fn dump_lazy_map<T: SubstateStore>(
     address: &Address,
     mid: &Mid,
@@ -0,0 +1,49 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use jsonrpc_core::futures::sync::mpsc;
+use parking_lot::Mutex;
+
+/// A single entity-creation event: the list of addresses committed by a `run`.
+pub type EntityEvent = Vec<String>;
+
+/// Fan-out broadcaster that pushes `receipt.new_entities` lists to every
+/// active `subscribe_entities` session. Each session holds the receiving half
+/// and forwards matching events into its pub-sub `Sink`.
+pub struct EntityBroadcast {
+    next_id: AtomicU64,
+    sinks: Mutex<Vec<(u64, mpsc::Sender<EntityEvent>)>>,
+}
+
+impl EntityBroadcast {
+    pub fn new() -> EntityBroadcast {
+        EntityBroadcast {
+            next_id: AtomicU64::new(1),
+            sinks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Register a new subscriber, returning its id and the receiver the
+    /// pub-sub session forwards into its `Sink`.
+    pub fn subscribe(&self) -> (u64, mpsc::Receiver<EntityEvent>) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = mpsc::channel(16);
+        self.sinks.lock().push((id, tx));
+        (id, rx)
+    }
+
+    /// Drop a subscriber by id, returning whether it was still registered.
+    pub fn unsubscribe(&self, id: u64) -> bool {
+        let mut sinks = self.sinks.lock();
+        let len = sinks.len();
+        sinks.retain(|(sid, _)| *sid != id);
+        sinks.len() != len
+    }
+
+    /// Publish an event to every live subscriber, dropping any whose receiver
+    /// has been closed.
+    pub fn publish(&self, event: EntityEvent) {
+        self.sinks
+            .lock()
+            .retain(|(_, tx)| tx.clone().try_send(event.clone()).is_ok());
+    }
+}
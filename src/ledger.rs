@@ -0,0 +1,272 @@
+use std::convert::{TryFrom, TryInto};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use radix_engine::ledger::{bootstrap, InMemorySubstateStore, SubstateStore};
+use radix_engine::model::{Component, LazyMap, NonFungible, Package, ResourceDef, Vault};
+use scrypto::buffer::{scrypto_decode, scrypto_encode};
+use scrypto::types::{Address, Mid, NonFungibleKey, Vid};
+
+/// Type tag prefixing every on-disk key so the flat key/value layout can be
+/// partitioned back into the typed indices the executor expects on open.
+const PACKAGE: u8 = 0;
+const COMPONENT: u8 = 1;
+const RESOURCE_DEF: u8 = 2;
+const LAZY_MAP: u8 = 3;
+const VAULT: u8 = 4;
+const NON_FUNGIBLE: u8 = 5;
+const META: u8 = 6;
+
+/// A `SubstateStore` that mirrors every substate to a key/value directory on
+/// disk, so a ledger deployed through `run` survives process restarts.
+///
+/// Reads are served from an in-memory [`InMemorySubstateStore`]; every write is
+/// both applied to that store and encoded to a file named after its type-tagged
+/// key. On open, a populated directory is replayed entry by entry to rebuild the
+/// in-memory indices; an empty one is bootstrapped *through* the store so the
+/// bootstrap substates (system package, XRD, account blueprint) are persisted.
+pub struct FileSubstateStore {
+    root: PathBuf,
+    inner: InMemorySubstateStore,
+}
+
+impl FileSubstateStore {
+    /// Open the store rooted at `root`. An empty store is bootstrapped through
+    /// `FileSubstateStore` so the bootstrap substates land on disk; a populated
+    /// one is replayed. Bootstrap is gated on whether the store actually holds
+    /// entries, not on mere directory existence, so a pre-existing empty dir
+    /// still gets a bootstrap rather than an empty ledger.
+    pub fn open<P: AsRef<Path>>(root: P) -> FileSubstateStore {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root).expect("Unable to create store directory");
+
+        let populated = fs::read_dir(&root)
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(false);
+
+        let store = FileSubstateStore {
+            root,
+            inner: InMemorySubstateStore::new(),
+        };
+
+        if populated {
+            let mut store = store;
+            store.replay();
+            store
+        } else {
+            // `bootstrap` drives the system package/XRD/account writes through
+            // our `put_*` methods, so they are mirrored to disk and survive the
+            // next restart.
+            bootstrap(store)
+        }
+    }
+
+    fn key_path(&self, tag: u8, id: &[u8]) -> PathBuf {
+        let mut name = format!("{:02x}", tag);
+        for b in id {
+            name.push_str(&format!("{:02x}", b));
+        }
+        self.root.join(name)
+    }
+
+    fn write(&self, tag: u8, id: &[u8], value: &[u8]) {
+        fs::write(self.key_path(tag, id), value).expect("Unable to persist substate");
+    }
+
+    /// Replay every on-disk entry through the in-memory store's `put_*`
+    /// methods, rebuilding its indices. A truncated (mid-`fs::write` crash) or
+    /// otherwise malformed entry is skipped and logged rather than panicking
+    /// startup — one bad file must not brick a durable node.
+    fn replay(&mut self) {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name().into_string().unwrap_or_default();
+            if let Err(err) = self.replay_entry(&name, &entry.path()) {
+                eprintln!("Skipping malformed store entry {}: {}", name, err);
+            }
+        }
+    }
+
+    /// Decode and apply a single on-disk entry, returning a description of any
+    /// decode failure so `replay` can skip it.
+    fn replay_entry(&mut self, name: &str, path: &Path) -> std::result::Result<(), String> {
+        if name.len() < 2 {
+            return Err("name shorter than a type tag".to_string());
+        }
+        let tag = decode_hex(&name[..2]).ok_or("non-hex type tag")?;
+        let id = decode_hex(&name[2..]).ok_or("non-hex key")?;
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+
+        match tag[0] {
+            PACKAGE => {
+                let address = Address::try_from(&id[..]).map_err(|_| "bad address")?;
+                self.inner
+                    .put_package(address, scrypto_decode(&bytes).map_err(|_| "bad package")?);
+            }
+            COMPONENT => {
+                let address = Address::try_from(&id[..]).map_err(|_| "bad address")?;
+                self.inner
+                    .put_component(address, scrypto_decode(&bytes).map_err(|_| "bad component")?);
+            }
+            RESOURCE_DEF => {
+                let address = Address::try_from(&id[..]).map_err(|_| "bad address")?;
+                self.inner.put_resource_def(
+                    address,
+                    scrypto_decode(&bytes).map_err(|_| "bad resource def")?,
+                );
+            }
+            LAZY_MAP => {
+                let (address, mid): (Address, Mid) =
+                    scrypto_decode(&id).map_err(|_| "bad lazy map key")?;
+                self.inner
+                    .put_lazy_map(address, mid, scrypto_decode(&bytes).map_err(|_| "bad lazy map")?);
+            }
+            VAULT => {
+                let (address, vid): (Address, Vid) =
+                    scrypto_decode(&id).map_err(|_| "bad vault key")?;
+                self.inner
+                    .put_vault(address, vid, scrypto_decode(&bytes).map_err(|_| "bad vault")?);
+            }
+            NON_FUNGIBLE => {
+                let (address, key): (Address, NonFungibleKey) =
+                    scrypto_decode(&id).map_err(|_| "bad non-fungible key")?;
+                self.inner.put_non_fungible(
+                    address,
+                    &key,
+                    scrypto_decode(&bytes).map_err(|_| "bad non-fungible")?,
+                );
+            }
+            META => {
+                if bytes.len() < 16 {
+                    return Err("truncated meta entry".to_string());
+                }
+                self.inner
+                    .set_epoch(u64::from_le_bytes(bytes[..8].try_into().unwrap()));
+                let nonce = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+                for _ in 0..nonce {
+                    self.inner.increase_nonce();
+                }
+            }
+            _ => return Err("unknown type tag".to_string()),
+        }
+        Ok(())
+    }
+
+    /// Persist the mutable bookkeeping (epoch and nonce) that has no address of
+    /// its own, so new-entity id generation stays monotonic across restarts.
+    /// Called after each committed transaction.
+    pub fn flush(&self) {
+        let mut meta = self.inner.get_epoch().to_le_bytes().to_vec();
+        meta.extend_from_slice(&self.inner.get_nonce().to_le_bytes());
+        self.write(META, b"state", &meta);
+    }
+}
+
+impl SubstateStore for FileSubstateStore {
+    fn get_resource_def(&self, address: Address) -> Option<ResourceDef> {
+        self.inner.get_resource_def(address)
+    }
+
+    fn put_resource_def(&mut self, address: Address, resource_def: ResourceDef) {
+        self.write(RESOURCE_DEF, &address.to_vec(), &scrypto_encode(&resource_def));
+        self.inner.put_resource_def(address, resource_def);
+    }
+
+    fn get_package(&self, address: Address) -> Option<Package> {
+        self.inner.get_package(address)
+    }
+
+    fn put_package(&mut self, address: Address, package: Package) {
+        self.write(PACKAGE, &address.to_vec(), &scrypto_encode(&package));
+        self.inner.put_package(address, package);
+    }
+
+    fn get_component(&self, address: Address) -> Option<Component> {
+        self.inner.get_component(address)
+    }
+
+    fn put_component(&mut self, address: Address, component: Component) {
+        self.write(COMPONENT, &address.to_vec(), &scrypto_encode(&component));
+        self.inner.put_component(address, component);
+    }
+
+    fn get_lazy_map(&self, component_address: &Address, mid: &Mid) -> Option<LazyMap> {
+        self.inner.get_lazy_map(component_address, mid)
+    }
+
+    fn put_lazy_map(&mut self, component_address: Address, mid: Mid, lazy_map: LazyMap) {
+        self.write(
+            LAZY_MAP,
+            &scrypto_encode(&(component_address, mid)),
+            &scrypto_encode(&lazy_map),
+        );
+        self.inner.put_lazy_map(component_address, mid, lazy_map);
+    }
+
+    fn get_vault(&self, component_address: &Address, vid: &Vid) -> Option<Vault> {
+        self.inner.get_vault(component_address, vid)
+    }
+
+    fn put_vault(&mut self, component_address: Address, vid: Vid, vault: Vault) {
+        self.write(
+            VAULT,
+            &scrypto_encode(&(component_address, vid)),
+            &scrypto_encode(&vault),
+        );
+        self.inner.put_vault(component_address, vid, vault);
+    }
+
+    fn get_non_fungible(
+        &self,
+        resource_address: Address,
+        key: &NonFungibleKey,
+    ) -> Option<NonFungible> {
+        self.inner.get_non_fungible(resource_address, key)
+    }
+
+    fn put_non_fungible(
+        &mut self,
+        resource_address: Address,
+        key: &NonFungibleKey,
+        non_fungible: NonFungible,
+    ) {
+        self.write(
+            NON_FUNGIBLE,
+            &scrypto_encode(&(resource_address, key.clone())),
+            &scrypto_encode(&non_fungible),
+        );
+        self.inner.put_non_fungible(resource_address, key, non_fungible);
+    }
+
+    fn get_epoch(&self) -> u64 {
+        self.inner.get_epoch()
+    }
+
+    fn set_epoch(&mut self, epoch: u64) {
+        self.inner.set_epoch(epoch);
+    }
+
+    fn get_nonce(&self) -> u64 {
+        self.inner.get_nonce()
+    }
+
+    fn increase_nonce(&mut self) {
+        self.inner.increase_nonce();
+    }
+}
+
+/// Decode a hex string, returning `None` on odd length or non-hex digits so a
+/// stray file in the store dir is skipped rather than panicking.
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
@@ -6,15 +6,20 @@ use std::sync::Arc;
 
 mod config;
 mod core;
+mod events;
 mod formatter;
+mod ledger;
+mod settings;
 
 lazy_static! {
     static ref CONFIG: Arc<RwLock<config::Config>> = Arc::new(RwLock::new(config::Config::new()));
+    static ref ENTITY_EVENTS: events::EntityBroadcast = events::EntityBroadcast::new();
 }
 
 fn main() {
-    let handle = std::thread::spawn(|| {
-        core::core_thread();
+    let settings = settings::Settings::load();
+    let handle = std::thread::spawn(move || {
+        core::core_thread(settings);
     });
 
     let _ = handle.join();
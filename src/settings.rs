@@ -0,0 +1,69 @@
+use std::fs;
+
+use serde::Deserialize;
+
+/// Runtime configuration for the RPC transports, read from a JSON file in
+/// `main()`. Every field has a default matching the previously hardcoded
+/// behaviour, so an absent or partial config still produces a working node.
+#[derive(Deserialize)]
+pub struct Settings {
+    #[serde(default = "default_http_bind")]
+    pub http_bind: String,
+    #[serde(default = "default_ws_bind")]
+    pub ws_bind: String,
+    #[serde(default = "default_threads")]
+    pub threads: usize,
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    #[serde(default = "default_ipc_path")]
+    pub ipc_path: String,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            http_bind: default_http_bind(),
+            ws_bind: default_ws_bind(),
+            threads: default_threads(),
+            cors_origins: Vec::new(),
+            allowed_hosts: Vec::new(),
+            ipc_path: default_ipc_path(),
+        }
+    }
+}
+
+impl Settings {
+    /// Load settings from the file named by `$RAD_RPC_CONFIG`, defaulting to
+    /// `rad-rpc.json` in the working directory. A missing file yields the
+    /// defaults; a malformed one aborts startup.
+    pub fn load() -> Settings {
+        let path = std::env::var("RAD_RPC_CONFIG").unwrap_or_else(|_| "rad-rpc.json".to_string());
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                serde_json::from_str(&contents).expect("Unable to parse config file")
+            }
+            Err(_) => Settings::default(),
+        }
+    }
+}
+
+fn default_http_bind() -> String {
+    "127.0.0.1:3030".to_string()
+}
+
+fn default_ws_bind() -> String {
+    "127.0.0.1:3031".to_string()
+}
+
+fn default_threads() -> usize {
+    100
+}
+
+/// Per-user domain socket path, keyed by `$USER` so concurrent nodes for
+/// different users don't collide.
+fn default_ipc_path() -> String {
+    let user = std::env::var("USER").unwrap_or_else(|_| "default".to_string());
+    format!("/tmp/rad-rpc-{}.ipc", user)
+}